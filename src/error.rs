@@ -0,0 +1,128 @@
+use std::fmt;
+
+/// Whether a [`CleanerError`] was caused by something the operator can fix
+/// (bad input, malformed rules) or by something internal to the run
+/// (an upstream API failure, an I/O error).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCategory {
+    User,
+    Internal,
+}
+
+/// A stable, machine-readable identifier for a [`CleanerError`] variant, plus
+/// its category and an optional link to further documentation. The `code`
+/// is what gets logged and sent in webhook payloads, so it must stay stable
+/// across releases even if the `Display` message text changes.
+#[derive(Debug, Clone)]
+pub struct ErrCode {
+    pub code: &'static str,
+    // Not read yet in-binary, but is part of the stable public shape of an
+    // error code (e.g. for a future "don't alert on user errors" policy).
+    #[allow(dead_code)]
+    pub category: ErrorCategory,
+    pub doc_link: Option<&'static str>,
+}
+
+impl ErrCode {
+    pub fn invalid(code: &'static str) -> Self {
+        ErrCode {
+            code,
+            category: ErrorCategory::User,
+            doc_link: None,
+        }
+    }
+
+    pub fn internal(code: &'static str) -> Self {
+        ErrCode {
+            code,
+            category: ErrorCategory::Internal,
+            doc_link: None,
+        }
+    }
+
+    pub fn with_doc(mut self, doc_link: &'static str) -> Self {
+        self.doc_link = Some(doc_link);
+        self
+    }
+}
+
+/// Unified error type for the cleaner's domain failures. Each variant maps to
+/// a stable [`ErrCode`] via [`CleanerError::err_code`] so that callers can log
+/// or report a failure without depending on `Display` text.
+#[derive(Debug)]
+pub enum CleanerError {
+    InvalidIndexPattern { pattern: String, message: String },
+    InvalidRuleConfig { pattern: String, message: String },
+    IndexNotAccessible { service: String, message: String },
+    DateParseFailed { index_name: String, message: String },
+    RulesParseFailed { message: String },
+    NotificationFailed { message: String },
+    TaskStoreFailed { message: String },
+    ReportExportFailed { message: String },
+}
+
+impl CleanerError {
+    pub fn err_code(&self) -> ErrCode {
+        match self {
+            CleanerError::InvalidIndexPattern { .. } => {
+                ErrCode::invalid("invalid_index_pattern")
+            }
+            CleanerError::InvalidRuleConfig { .. } => ErrCode::invalid("invalid_rule_config"),
+            CleanerError::IndexNotAccessible { .. } => {
+                ErrCode::internal("index_not_accessible")
+            }
+            CleanerError::DateParseFailed { .. } => ErrCode::invalid("date_parse_failed"),
+            CleanerError::RulesParseFailed { .. } => ErrCode::invalid("rules_parse_failed"),
+            CleanerError::NotificationFailed { .. } => ErrCode::internal("notification_failed"),
+            CleanerError::TaskStoreFailed { .. } => ErrCode::internal("task_store_failed"),
+            CleanerError::ReportExportFailed { .. } => ErrCode::internal("report_export_failed"),
+        }
+        .with_doc("https://github.com/oleewere/opensearch-index-cleaner#error-codes")
+    }
+
+    pub fn code(&self) -> &'static str {
+        self.err_code().code
+    }
+}
+
+impl fmt::Display for CleanerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let code = self.code();
+        match self {
+            CleanerError::InvalidIndexPattern { pattern, message } => write!(
+                f,
+                "[{}] invalid index pattern '{}': {}",
+                code, pattern, message
+            ),
+            CleanerError::InvalidRuleConfig { pattern, message } => write!(
+                f,
+                "[{}] invalid rule for pattern '{}': {}",
+                code, pattern, message
+            ),
+            CleanerError::IndexNotAccessible { service, message } => write!(
+                f,
+                "[{}] could not list indices for service '{}': {}",
+                code, service, message
+            ),
+            CleanerError::DateParseFailed { index_name, message } => write!(
+                f,
+                "[{}] could not parse a date out of index '{}': {}",
+                code, index_name, message
+            ),
+            CleanerError::RulesParseFailed { message } => {
+                write!(f, "[{}] could not parse rules file: {}", code, message)
+            }
+            CleanerError::NotificationFailed { message } => {
+                write!(f, "[{}] notification delivery failed: {}", code, message)
+            }
+            CleanerError::TaskStoreFailed { message } => {
+                write!(f, "[{}] task store operation failed: {}", code, message)
+            }
+            CleanerError::ReportExportFailed { message } => {
+                write!(f, "[{}] report export failed: {}", code, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CleanerError {}
@@ -2,21 +2,29 @@ use aiven_rs::service::types_elasticsearch::Index;
 use aiven_rs::AivenClient;
 use chrono::{NaiveDate, Utc};
 use dotenv::dotenv;
-use reqwest::{Response, Url};
+use futures::stream::{self, StreamExt};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use serde_yaml;
 use std::env;
-use std::error::Error;
 use std::fs::File;
 use std::io::prelude::*;
 use std::process::exit;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
 use log::{error, warn};
 
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
-struct Report {
-    pub name: String,
-    pub formatted_size: String,
-}
+mod error;
+mod metrics;
+mod notifier;
+mod report_export;
+mod task_store;
+
+use error::CleanerError;
+use metrics::{MetricsRegistry, ServiceMetrics};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Instant;
+use task_store::{TaskStatus, TaskStore};
 
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 struct Service {
@@ -31,22 +39,50 @@ struct SummaryReport {
     name: String,
 }
 
+/// A single eviction rule for indices matching `index_pattern`. Exactly one
+/// of `age_threshold`, `size_budget_bytes`, or `keep_newest` must be set,
+/// selecting the rule's mode (setting more than one is rejected with
+/// [`CleanerError::InvalidRuleConfig`]; a rule with none of them set is
+/// skipped with a warning):
+/// - `age_threshold`: delete indices older than this many days (the
+///   original mode).
+/// - `size_budget_bytes`: delete the oldest matching indices until the
+///   pattern's total size drops to the budget.
+/// - `keep_newest`: delete all but the newest N matching indices.
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
 struct Rule {
     index_pattern: String,
-    age_threshold: i64,
+    #[serde(default)]
+    age_threshold: Option<i64>,
     date_pattern: Option<String>,
+    /// Regex used to pull the date substring out of the index name when it
+    /// isn't simply the last 10 characters. The first capture group is used
+    /// if present, otherwise the whole match.
+    #[serde(default)]
+    date_capture_regex: Option<String>,
+    #[serde(default)]
+    size_budget_bytes: Option<u64>,
+    #[serde(default)]
+    keep_newest: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
-struct ServiceResult {
+pub(crate) struct ServiceResult {
     pub name: String,
     pub size: u64,
     pub success: bool,
+    /// Stable error code (see [`error::CleanerError::code`]) set when `success` is `false`.
+    pub error_code: Option<String>,
+    /// The `index_pattern` of the rule that matched this index, if any.
+    pub matched_rule: Option<String>,
+    /// The task store's id for this deletion attempt, when a `TaskStore` is configured.
+    pub task_id: Option<String>,
+    /// The task store's record timestamp for this deletion attempt, when a `TaskStore` is configured.
+    pub timestamp_secs: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Debug)]
-struct ServiceResults {
+pub(crate) struct ServiceResults {
     pub deletes: Vec<ServiceResult>,
     pub total: u64,
     pub total_remaining: u64,
@@ -55,19 +91,6 @@ struct ServiceResults {
     pub reports: Vec<(String, String)>,
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
-struct NotificationData {
-    pub attachments: Vec<Attachment>,
-}
-
-#[derive(Serialize, Deserialize, PartialEq, Debug)]
-struct Attachment {
-    pub color: String,
-    pub text: String,
-    pub title: String,
-    pub title_link: Option<String>,
-}
-
 fn sizeof_fmt(mut num: u64) -> String {
     let units = ["", "Ki", "Mi", "Gi", "Ti", "Pi", "Ei", "Zi"];
     for unit in units.iter() {
@@ -79,28 +102,85 @@ fn sizeof_fmt(mut num: u64) -> String {
     format!("{}Yi{}", num, "B")
 }
 
+/// Picks how many deletions to run concurrently: small runs stay effectively
+/// serial, huge runs fan out up to `worker_ceiling`, with each worker
+/// responsible for roughly `TARGET_BATCHES` deletions.
+fn adaptive_concurrency(total_to_delete: usize, worker_ceiling: usize) -> usize {
+    const TARGET_BATCHES: usize = 4;
+    if total_to_delete == 0 {
+        return 1;
+    }
+    (total_to_delete / TARGET_BATCHES).clamp(1, worker_ceiling.max(1))
+}
+
 fn filter_indices_by_pattern<'a>(
-    indices: &'a Vec<Index>,
+    indices: &'a [Index],
     index_pattern: &'a str,
-) -> Vec<&'a Index> {
-    let re = fnmatch_regex::glob_to_regex(index_pattern).unwrap();
+) -> Result<Vec<&'a Index>, CleanerError> {
+    let re = fnmatch_regex::glob_to_regex(index_pattern).map_err(|e| {
+        CleanerError::InvalidIndexPattern {
+            pattern: index_pattern.to_string(),
+            message: e.to_string(),
+        }
+    })?;
     let indexes = indices
         .iter()
         .filter(|index| re.is_match(&index.index_name))
         .collect::<Vec<&Index>>();
-    return indexes;
+    Ok(indexes)
 }
 
-fn days_between_today_and_date(date_pattern: &str, date_str: &str) -> Result<i64, Box<dyn Error>> {
+fn days_between_today_and_date(
+    date_pattern: &str,
+    date_str: &str,
+) -> Result<i64, CleanerError> {
     let today = Utc::now().date_naive();
-    let input_date = match NaiveDate::parse_from_str(date_str, date_pattern) {
-        Ok(date_time) => date_time,
-        Err(e) => return Err(e.into()),
-    };
+    let input_date =
+        NaiveDate::parse_from_str(date_str, date_pattern).map_err(|e| CleanerError::DateParseFailed {
+            index_name: date_str.to_string(),
+            message: e.to_string(),
+        })?;
     let days = (today - input_date).num_days();
     Ok(days)
 }
 
+/// Pulls the date substring out of an index name: via `rule.date_capture_regex`
+/// (first capture group, or the whole match if there is none) when set,
+/// otherwise the legacy assumption that the date occupies the last 10
+/// characters of the name.
+fn extract_date_str(index_name: &str, rule: &Rule) -> Result<String, CleanerError> {
+    match &rule.date_capture_regex {
+        Some(pattern) => {
+            let re = Regex::new(pattern).map_err(|e| CleanerError::InvalidIndexPattern {
+                pattern: pattern.clone(),
+                message: e.to_string(),
+            })?;
+            let caps = re.captures(index_name).ok_or_else(|| CleanerError::DateParseFailed {
+                index_name: index_name.to_string(),
+                message: format!("date_capture_regex '{}' did not match", pattern),
+            })?;
+            let date_match = caps.get(1).or_else(|| caps.get(0)).ok_or_else(|| {
+                CleanerError::DateParseFailed {
+                    index_name: index_name.to_string(),
+                    message: format!("date_capture_regex '{}' has no match group", pattern),
+                }
+            })?;
+            Ok(date_match.as_str().to_string())
+        }
+        None => {
+            if index_name.len() < 10 {
+                return Err(CleanerError::DateParseFailed {
+                    index_name: index_name.to_string(),
+                    message: "index name shorter than the expected 10-character date suffix"
+                        .to_string(),
+                });
+            }
+            Ok(index_name[index_name.len() - 10..].to_string())
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn cleanup_service(
     aiven_client: &AivenClient,
     project: &str,
@@ -108,11 +188,20 @@ async fn cleanup_service(
     dry_run: bool,
     rules: &Vec<Rule>,
     summary_reports: &Vec<SummaryReport>,
-) -> ServiceResults {
+    already_succeeded: &HashSet<(String, String)>,
+    task_store: Option<&mut TaskStore>,
+) -> Result<ServiceResults, CleanerError> {
     let mut total_data_deleted = 0;
     let mut num_failures = 0;
     let es_api = aiven_client.service_elasticsearch();
-    let indices = es_api.list_indexes(project, name).await.unwrap().indexes;
+    let indices = es_api
+        .list_indexes(project, name)
+        .await
+        .map_err(|e| CleanerError::IndexNotAccessible {
+            service: name.to_string(),
+            message: e.to_string(),
+        })?
+        .indexes;
     let mut full_index_size = 0;
     for index in &indices {
         let act_index_size = index.size;
@@ -123,7 +212,7 @@ async fn cleanup_service(
     for sum_report in summary_reports {
         let report_pattern = &sum_report.pattern;
         let report_name = &sum_report.name;
-        let indexes = filter_indices_by_pattern(&indices, report_pattern);
+        let indexes = filter_indices_by_pattern(&indices, report_pattern)?;
         if !indexes.is_empty() {
             let mut partial_full_index_size = 0;
             for index in &indexes {
@@ -137,15 +226,20 @@ async fn cleanup_service(
     }
     let mut results: Vec<ServiceResult> = vec![];
     let mut index_already_deleted = Vec::new();
+    let mut to_delete: Vec<(String, u64, String)> = vec![];
     for rule in rules {
         let index_pattern = &rule.index_pattern;
-        let age_threshold = rule.age_threshold;
         let date_pattern = rule
             .date_pattern
             .clone()
-            .or(Some("%Y.%m.%d".to_string()))
-            .unwrap();
-        let indexes = filter_indices_by_pattern(&indices, index_pattern);
+            .unwrap_or_else(|| "%Y.%m.%d".to_string());
+        let indexes = filter_indices_by_pattern(&indices, index_pattern)?;
+
+        // Phase 1: drop already-handled indices and pull each remaining
+        // index's date substring, so the size/count rule modes below can
+        // sort matches oldest-first regardless of where the date lives in
+        // the name.
+        let mut candidates: Vec<(String, u64, String)> = vec![];
         for index in &indexes {
             let index_name = index.index_name.clone();
             if index_already_deleted.contains(&index_name) {
@@ -159,40 +253,208 @@ async fn cleanup_service(
                 println!("Index with name {} is protected.", index_name);
                 continue;
             }
-            let index_date_str = &index_name[index_name.len() - 10..];
-            let age = days_between_today_and_date(&date_pattern, index_date_str.clone()).unwrap();
-            if age > age_threshold {
-                let mut result = ServiceResult {
-                    name: index_name.to_owned(),
-                    size: index.size,
-                    success: false,
+            if already_succeeded.contains(&(name.to_string(), index_name.clone())) {
+                println!(
+                    "Index with name {} was already deleted by a prior run, resuming past it.",
+                    index_name
+                );
+                continue;
+            }
+            match extract_date_str(&index_name, rule) {
+                Ok(date_str) => candidates.push((index_name, index.size, date_str)),
+                Err(err) => {
+                    warn!("Skipping index {}: {}", index_name, err);
+                    num_failures += 1;
+                    results.push(ServiceResult {
+                        name: index_name,
+                        size: index.size,
+                        success: false,
+                        error_code: Some(err.code().to_string()),
+                        matched_rule: Some(index_pattern.clone()),
+                        task_id: None,
+                        timestamp_secs: None,
+                    });
+                }
+            }
+        }
+
+        // Phase 2: pick which candidates to evict, based on the rule's mode.
+        let modes_set = rule.age_threshold.is_some() as u8
+            + rule.size_budget_bytes.is_some() as u8
+            + rule.keep_newest.is_some() as u8;
+        if modes_set > 1 {
+            return Err(CleanerError::InvalidRuleConfig {
+                pattern: index_pattern.clone(),
+                message: "exactly one of age_threshold, size_budget_bytes, or keep_newest must be set"
+                    .to_string(),
+            });
+        }
+        if let Some(age_threshold) = rule.age_threshold {
+            for (index_name, size, date_str) in candidates {
+                let age = match days_between_today_and_date(&date_pattern, &date_str) {
+                    Ok(age) => age,
+                    Err(err) => {
+                        warn!("Skipping index {}: {}", index_name, err);
+                        num_failures += 1;
+                        results.push(ServiceResult {
+                            name: index_name,
+                            size,
+                            success: false,
+                            error_code: Some(err.code().to_string()),
+                            matched_rule: Some(index_pattern.clone()),
+                            task_id: None,
+                            timestamp_secs: None,
+                        });
+                        continue;
+                    }
                 };
-                if dry_run {
-                    println!(
-                        "Deleting index {} with size {} bytes (dry-run)",
-                        index_name, index.size
-                    );
-                } else {
-                    println!(
-                        "Deleting index {} with size {} bytes",
-                        index_name, index.size
-                    );
-                    let del_res = es_api.delete_index(project, name, index_name.as_str()).await;
-                    match del_res {
-                        Ok(_) => {},
-                        Err(err) => {
-                            warn!("Aiven error: {}", err);
-                            num_failures+=1;
-                        },
+                if age > age_threshold {
+                    index_already_deleted.push(index_name.clone());
+                    to_delete.push((index_name, size, index_pattern.clone()));
+                }
+            }
+        } else if rule.size_budget_bytes.is_some() || rule.keep_newest.is_some() {
+            let mut dated = Vec::with_capacity(candidates.len());
+            for (index_name, size, date_str) in candidates {
+                match NaiveDate::parse_from_str(&date_str, &date_pattern) {
+                    Ok(date) => dated.push((index_name, size, date)),
+                    Err(e) => {
+                        let err = CleanerError::DateParseFailed {
+                            index_name: index_name.clone(),
+                            message: e.to_string(),
+                        };
+                        warn!("Skipping index {}: {}", index_name, err);
+                        num_failures += 1;
+                        results.push(ServiceResult {
+                            name: index_name,
+                            size,
+                            success: false,
+                            error_code: Some(err.code().to_string()),
+                            matched_rule: Some(index_pattern.clone()),
+                            task_id: None,
+                            timestamp_secs: None,
+                        });
                     }
                 }
-                index_already_deleted.push(index_name);
-                total_data_deleted += index.size;
-                result.success = true;
-                results.push(result);
             }
+
+            if let Some(budget) = rule.size_budget_bytes {
+                dated.sort_by_key(|(_, _, date)| *date);
+                let mut remaining_total: u64 = dated.iter().map(|(_, size, _)| size).sum();
+                for (index_name, size, _date) in dated {
+                    if remaining_total <= budget {
+                        break;
+                    }
+                    remaining_total = remaining_total.saturating_sub(size);
+                    index_already_deleted.push(index_name.clone());
+                    to_delete.push((index_name, size, index_pattern.clone()));
+                }
+            } else if let Some(keep_newest) = rule.keep_newest {
+                dated.sort_by_key(|(_, _, date)| std::cmp::Reverse(*date));
+                for (index_name, size, _date) in dated.into_iter().skip(keep_newest as usize) {
+                    index_already_deleted.push(index_name.clone());
+                    to_delete.push((index_name, size, index_pattern.clone()));
+                }
+            }
+        } else {
+            warn!(
+                "Rule for pattern {} has none of age_threshold, size_budget_bytes, or keep_newest set; skipping.",
+                index_pattern
+            );
         }
     }
+
+    if dry_run {
+        for (index_name, size, rule_pattern) in &to_delete {
+            println!("Deleting index {} with size {} bytes (dry-run)", index_name, size);
+            total_data_deleted += size;
+            results.push(ServiceResult {
+                name: index_name.clone(),
+                size: *size,
+                success: true,
+                error_code: None,
+                matched_rule: Some(rule_pattern.clone()),
+                task_id: None,
+                timestamp_secs: None,
+            });
+        }
+    } else {
+        let worker_ceiling: usize = env::var("CLEANUP_MAX_CONCURRENCY")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(8);
+        let concurrency = adaptive_concurrency(to_delete.len(), worker_ceiling);
+        let total_data_deleted_atomic = AtomicU64::new(0);
+        let num_failures_atomic = AtomicU64::new(0);
+        let results_mutex = Mutex::new(Vec::with_capacity(to_delete.len()));
+        let task_store_mutex = task_store.map(Mutex::new);
+
+        stream::iter(to_delete.into_iter().map(|(index_name, size, rule_pattern)| {
+            let total_data_deleted_atomic = &total_data_deleted_atomic;
+            let num_failures_atomic = &num_failures_atomic;
+            let results_mutex = &results_mutex;
+            let task_store_mutex = task_store_mutex.as_ref();
+            let es_api = &es_api;
+            async move {
+                println!("Deleting index {} with size {} bytes", index_name, size);
+                if let Some(store_mutex) = task_store_mutex {
+                    store_mutex
+                        .lock()
+                        .unwrap()
+                        .record_index(name, &index_name, &rule_pattern, TaskStatus::Processing, None)
+                        .ok();
+                }
+                let del_res = es_api.delete_index(project, name, index_name.as_str()).await;
+                let error_code = match del_res {
+                    Ok(_) => None,
+                    Err(err) => {
+                        warn!("Aiven error: {}", err);
+                        num_failures_atomic.fetch_add(1, Ordering::SeqCst);
+                        Some("index_not_accessible".to_string())
+                    }
+                };
+                let success = error_code.is_none();
+                let mut task_id = None;
+                let mut timestamp_secs = None;
+                if let Some(store_mutex) = task_store_mutex {
+                    let status = if success {
+                        TaskStatus::Succeeded
+                    } else {
+                        TaskStatus::Failed
+                    };
+                    if let Ok(record) = store_mutex.lock().unwrap().record_index(
+                        name,
+                        &index_name,
+                        &rule_pattern,
+                        status,
+                        error_code.clone(),
+                    ) {
+                        task_id = Some(record.task_id);
+                        timestamp_secs = Some(record.timestamp_secs);
+                    }
+                }
+                if success {
+                    total_data_deleted_atomic.fetch_add(size, Ordering::SeqCst);
+                }
+                results_mutex.lock().unwrap().push(ServiceResult {
+                    name: index_name,
+                    size,
+                    success,
+                    error_code,
+                    matched_rule: Some(rule_pattern),
+                    task_id,
+                    timestamp_secs,
+                });
+            }
+        }))
+        .buffer_unordered(concurrency)
+        .collect::<Vec<()>>()
+        .await;
+
+        total_data_deleted += total_data_deleted_atomic.load(Ordering::SeqCst);
+        num_failures += num_failures_atomic.load(Ordering::SeqCst);
+        results.append(&mut results_mutex.into_inner().unwrap());
+    }
     let total_remaining_size = full_index_size - total_data_deleted;
     let human_readable_total_size = sizeof_fmt(total_data_deleted);
     let human_readable_total_remaining_size = sizeof_fmt(total_remaining_size);
@@ -201,118 +463,41 @@ async fn cleanup_service(
         name, human_readable_total_size, human_readable_total_remaining_size
     );
     println!("{}", msg);
-    return ServiceResults {
+    Ok(ServiceResults {
         deletes: results,
         total: total_data_deleted,
         total_remaining: full_index_size - total_data_deleted,
         total_human_readable_msg: msg,
         failures: num_failures,
-        reports: reports,
-    };
+        reports,
+    })
 }
 
-async fn send_notification(
-    opensearch_cleanup_webhook_url: String,
-    all_results: Vec<(String, ServiceResults)>,
-    aiven_project: String,
-) -> Result<Response, reqwest::Error> {
-    let mut short_descriptions = Vec::new();
-    let mut all_deleted_indexes = Vec::new();
-    let mut has_failures = false;
-    let mut all_report_texts = Vec::new();
-
-    for (key, service_result) in all_results {
-        let failures = service_result.deletes.iter().filter(|r| !r.success).count();
-        if failures > 0 {
-            has_failures = true;
-        }
-        let status = if failures == 0 {
-            ":white_check_mark:"
-        } else {
-            ":x:"
-        };
-        short_descriptions.push(format!(
-            "{} - {}",
-            service_result.total_human_readable_msg,
-            status
-        ));
-
-        for deleted_index in service_result.deletes {
-            let status = if deleted_index.success {
-                ":white_check_mark:"
-            } else {
-                ":x:"
-            };
-            if deleted_index.success {
-                all_deleted_indexes.push(format!(
-                    "{} - {} ({}) - size: {} bytes",
-                    status, deleted_index.name, key, deleted_index.size
-                ));
-            } else {
-                all_deleted_indexes.push(format!("{} - {} ({})", status, deleted_index.name, key));
-            }
-        }
-        let service_report_summary_list = service_result.reports;
-        if !service_report_summary_list.is_empty() {
-            let mut summary_texts = vec![];
-            for service_report_summary in service_report_summary_list {
-                summary_texts.push(format!(
-                    "{}: {}",
-                    service_report_summary.0, service_report_summary.1
-                ));
-            }
-            let report_body = summary_texts.join("\n");
-            let report_text = format!("Summary for {} (pre-cleanup):\n{}\n", key, report_body);
-            all_report_texts.push(report_text);
-        }
+/// Builds a stand-in [`ServiceResults`] for a service that failed before any
+/// index could be inspected (e.g. the list-indices API call itself failed),
+/// so the failure still shows up in logs and the webhook payload instead of
+/// silently dropping the service from the run.
+fn error_service_results(service: &str, err: &CleanerError) -> ServiceResults {
+    let msg = format!("Cleanup failed for {} service: {}", service, err);
+    ServiceResults {
+        deletes: vec![ServiceResult {
+            name: service.to_string(),
+            size: 0,
+            success: false,
+            error_code: Some(err.code().to_string()),
+            matched_rule: None,
+            task_id: None,
+            timestamp_secs: None,
+        }],
+        total: 0,
+        total_remaining: 0,
+        total_human_readable_msg: msg,
+        failures: 1,
+        reports: vec![],
     }
-
-    let all_report_texts_value = if !all_report_texts.is_empty() {
-        format!("\n\n{}", all_report_texts.join("\n"))
-    } else {
-        "".to_string()
-    };
-    let details_text = if !all_deleted_indexes.is_empty() {
-        format!("\n\nDetails:\n\n{}", all_deleted_indexes.join("\n"))
-    } else {
-        "\n\nNot found any old indices by pre-defined rules.".to_string()
-    };
-    let output_text = format!(
-        "{}{}{}",
-        short_descriptions.join("\n"),
-        all_report_texts_value,
-        details_text
-    );
-    let title = format!("{} - Opensearch index cleanup", aiven_project);
-    let color = if has_failures { "#E01E5A" } else { "#2EB67D" };
-    let title_link_var =
-        env::var("NOTIFICATION_TITLE_LINK").unwrap_or_else(|_| "".to_string());
-    let title_link = match !title_link_var.is_empty() {
-        true => Some(title_link_var),
-        false => None,
-    };
-    let attachment = Attachment {
-        title: title,
-        title_link: title_link,
-        text: output_text,
-        color: color.to_string(),
-    };
-    let notification_data = NotificationData {
-        attachments: vec![attachment],
-    };
-    let req_str = serde_json::to_string(&notification_data).unwrap();
-    let client = reqwest::Client::new();
-    let url = Url::parse(opensearch_cleanup_webhook_url.as_str()).unwrap();
-    let result = client
-        .post(url)
-        .header("Content-type", "application/json")
-        .body(req_str)
-        .send()
-        .await;
-    return result;
 }
 
-async fn cleanup() -> Result<bool, Box<dyn Error>> {
+async fn cleanup() -> Result<bool, CleanerError> {
     let rules_file = env::var("RULES_FILE").unwrap_or_else(|_| "".to_string());
     let cleanup_dry_run: bool = env::var("CLEANUP_DRY_RUN")
             .unwrap_or("false".to_string())
@@ -324,7 +509,9 @@ async fn cleanup() -> Result<bool, Box<dyn Error>> {
         Ok(file) => file,
         Err(err) => {
             println!("Error opening file: {}", err);
-            return Err(err.into());
+            return Err(CleanerError::RulesParseFailed {
+                message: err.to_string(),
+            });
         },
     };
 
@@ -333,7 +520,9 @@ async fn cleanup() -> Result<bool, Box<dyn Error>> {
         Ok(_) => {}
         Err(err) => {
             println!("Error reading file: {}", err);
-            return Err(err.into());
+            return Err(CleanerError::RulesParseFailed {
+                message: err.to_string(),
+            });
         }
     };
 
@@ -341,47 +530,110 @@ async fn cleanup() -> Result<bool, Box<dyn Error>> {
         Ok(rules) => rules,
         Err(err) => {
             println!("Error parsing YAML: {}", err);
-            return Err(err.into());
+            return Err(CleanerError::RulesParseFailed {
+                message: err.to_string(),
+            });
         }
     };
     let aiven_client = AivenClient::from_token("https://api.aiven.io", "v1", &aiven_api_token);
+
+    let task_store_dir = env::var("TASK_STORE_DIR").unwrap_or_else(|_| "".to_string());
+    let cleanup_resume: bool = env::var("CLEANUP_RESUME")
+        .unwrap_or("false".to_string())
+        .parse()
+        .unwrap_or(false);
+    let already_succeeded = if cleanup_resume && !task_store_dir.is_empty() {
+        task_store::load_succeeded_indices(&task_store_dir)?
+    } else {
+        HashSet::new()
+    };
+    let mut task_store = if task_store_dir.is_empty() {
+        None
+    } else {
+        let run_id = format!("run-{}", task_store::now_secs());
+        Some(TaskStore::new(&task_store_dir, &run_id)?)
+    };
+
+    let metrics_registry = env::var("METRICS_PORT")
+        .ok()
+        .and_then(|v| v.parse::<u16>().ok())
+        .map(|port| {
+            let registry = Arc::new(MetricsRegistry::new());
+            metrics::serve(port, Arc::clone(&registry));
+            registry
+        });
+
     let mut all_results = vec![];
     for service_obj in service_rules {
         let name = service_obj.service.clone();
-        all_results.push((
-            name.clone(),
-            cleanup_service(
-                &aiven_client,
-                &aiven_project,
-                &name.as_str(),
-                cleanup_dry_run,
-                &service_obj.rules,
-                &service_obj.summary_reports,
-            )
-            .await,
-        ));
-    }
-    let opensearch_cleanup_webhook_url =
-        env::var("NOTIFICATION_WEBHOOK_URL").unwrap_or_else(|_| "".to_string());
-    if !cleanup_dry_run && !opensearch_cleanup_webhook_url.is_empty() {
-        let res =
-            send_notification(opensearch_cleanup_webhook_url, all_results, aiven_project).await;
-        match res {
-            Ok(res) => {
-                if !res.status().is_success() {
-                    warn!("Notification response is not successful: {}", res.status().as_str());
-                    let t = res.text().await.unwrap();
-                    warn!("res: {}", t);
-                    return Ok(false)
-                }
-            },
+        let run_started_at = Instant::now();
+        let result = cleanup_service(
+            &aiven_client,
+            &aiven_project,
+            name.as_str(),
+            cleanup_dry_run,
+            &service_obj.rules,
+            &service_obj.summary_reports,
+            &already_succeeded,
+            task_store.as_mut(),
+        )
+        .await;
+        let service_results = match result {
+            Ok(service_results) => service_results,
             Err(err) => {
-                error!("Notification error: {}", err);
-                return Err(err.into())
-            },
+                error!("Cleanup failed for {} service: {}", name, err);
+                error_service_results(&name, &err)
+            }
+        };
+        if let Some(registry) = &metrics_registry {
+            registry.record(ServiceMetrics {
+                service: name.clone(),
+                aiven_project: aiven_project.clone(),
+                bytes_deleted: service_results.total,
+                indices_deleted: service_results.deletes.iter().filter(|r| r.success).count() as u64,
+                failures: service_results.failures,
+                remaining_index_size: service_results.total_remaining,
+                run_duration_seconds: run_started_at.elapsed().as_secs_f64(),
+            });
+        }
+        all_results.push((name, service_results));
+    }
+    if let Err(err) = report_export::export(&all_results) {
+        // Same reasoning as the notifier error below: the cleanup already
+        // ran to completion, so a failure to export the report shouldn't
+        // swallow the one channel (notifications) that would tell an
+        // operator something went wrong with the run.
+        error!("Report export error: {}", err);
+    }
+
+    if !cleanup_dry_run {
+        if let Err(err) = notifier::send_all(&all_results, &aiven_project).await {
+            // The cleanup itself already ran to completion; a failure to notify
+            // about it shouldn't flip the process's exit code to failure.
+            error!("Notification error: {}", err);
+        }
+    }
+
+    if metrics_registry.is_some() {
+        // This is a one-shot batch job: without this, the process (and the
+        // metrics thread with it) exits the instant cleanup finishes, giving
+        // a Pushgateway or scraper essentially no window to hit /metrics.
+        // Hold the process open for a grace period so a scrape has a real
+        // chance to land; set to 0 to restore the old exit-immediately behavior.
+        let grace_secs: u64 = env::var("METRICS_EXIT_GRACE_SECS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(15);
+        if grace_secs > 0 {
+            println!(
+                "Holding process open for {}s so a scraper can read /metrics before exit.",
+                grace_secs
+            );
+            tokio::time::sleep(std::time::Duration::from_secs(grace_secs)).await;
         }
     }
-    return Ok(true);
+
+    Ok(true)
 }
 
 #[tokio::main]
@@ -396,3 +648,34 @@ async fn main() {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::adaptive_concurrency;
+
+    #[test]
+    fn no_items_still_gets_one_worker() {
+        assert_eq!(adaptive_concurrency(0, 8), 1);
+    }
+
+    #[test]
+    fn tiny_batch_stays_below_one_worker_per_batch_target() {
+        assert_eq!(adaptive_concurrency(3, 8), 1);
+    }
+
+    #[test]
+    fn batch_sized_to_exactly_fill_one_worker() {
+        assert_eq!(adaptive_concurrency(4, 8), 1);
+        assert_eq!(adaptive_concurrency(8, 8), 2);
+    }
+
+    #[test]
+    fn huge_batch_is_clamped_to_worker_ceiling() {
+        assert_eq!(adaptive_concurrency(10_000, 8), 8);
+    }
+
+    #[test]
+    fn zero_worker_ceiling_still_allows_one_worker() {
+        assert_eq!(adaptive_concurrency(10_000, 0), 1);
+    }
+}
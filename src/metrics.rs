@@ -0,0 +1,137 @@
+use log::error;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// One service's worth of Prometheus samples for a single cleanup run,
+/// labeled by `service` and `aiven_project`.
+#[derive(Debug, Clone, Default)]
+pub struct ServiceMetrics {
+    pub service: String,
+    pub aiven_project: String,
+    pub bytes_deleted: u64,
+    pub indices_deleted: u64,
+    pub failures: u64,
+    pub remaining_index_size: u64,
+    pub run_duration_seconds: f64,
+}
+
+/// In-memory Prometheus text-exposition registry. There is no Pushgateway in
+/// this deployment, so the registry just holds each service's last-recorded
+/// numbers for `/metrics` to serve until the process exits.
+#[derive(Default)]
+pub struct MetricsRegistry {
+    services: Mutex<Vec<ServiceMetrics>>,
+}
+
+impl MetricsRegistry {
+    pub fn new() -> Self {
+        MetricsRegistry {
+            services: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn record(&self, metrics: ServiceMetrics) {
+        self.services.lock().unwrap().push(metrics);
+    }
+
+    fn render(&self) -> String {
+        let services = self.services.lock().unwrap();
+        let mut out = String::new();
+        fn gauge_or_counter(
+            out: &mut String,
+            services: &[ServiceMetrics],
+            name: &str,
+            kind: &str,
+            help: &str,
+            value: impl Fn(&ServiceMetrics) -> String,
+        ) {
+            out.push_str(&format!("# HELP {} {}\n", name, help));
+            out.push_str(&format!("# TYPE {} {}\n", name, kind));
+            for m in services.iter() {
+                out.push_str(&format!(
+                    "{}{{service=\"{}\",aiven_project=\"{}\"}} {}\n",
+                    name,
+                    m.service,
+                    m.aiven_project,
+                    value(m)
+                ));
+            }
+        }
+        gauge_or_counter(
+            &mut out,
+            &services,
+            "opensearch_cleaner_bytes_deleted",
+            "counter",
+            "Total bytes deleted in the last cleanup run",
+            |m| m.bytes_deleted.to_string(),
+        );
+        gauge_or_counter(
+            &mut out,
+            &services,
+            "opensearch_cleaner_indices_deleted",
+            "counter",
+            "Total indices deleted in the last cleanup run",
+            |m| m.indices_deleted.to_string(),
+        );
+        gauge_or_counter(
+            &mut out,
+            &services,
+            "opensearch_cleaner_failures",
+            "counter",
+            "Total per-index failures in the last cleanup run",
+            |m| m.failures.to_string(),
+        );
+        gauge_or_counter(
+            &mut out,
+            &services,
+            "opensearch_cleaner_remaining_index_size_bytes",
+            "gauge",
+            "Remaining index size after the last cleanup run",
+            |m| m.remaining_index_size.to_string(),
+        );
+        gauge_or_counter(
+            &mut out,
+            &services,
+            "opensearch_cleaner_run_duration_seconds",
+            "gauge",
+            "Duration of the last cleanup run for this service",
+            |m| m.run_duration_seconds.to_string(),
+        );
+        out
+    }
+}
+
+/// Starts a background thread serving `/metrics` on `0.0.0.0:{port}` for as
+/// long as the process runs. Since this is a one-shot batch job, the caller
+/// is expected to hold the process open for a grace period after the run
+/// finishes (see `METRICS_EXIT_GRACE_SECS` in `main`) so a Pushgateway or
+/// scraper has a real window to read the totals.
+pub fn serve(port: u16, registry: Arc<MetricsRegistry>) {
+    thread::spawn(move || {
+        let listener = match TcpListener::bind(("0.0.0.0", port)) {
+            Ok(listener) => listener,
+            Err(err) => {
+                error!("Could not bind metrics listener on port {}: {}", port, err);
+                return;
+            }
+        };
+        for stream in listener.incoming().flatten() {
+            let registry = Arc::clone(&registry);
+            thread::spawn(move || handle_connection(stream, &registry));
+        }
+    });
+}
+
+fn handle_connection(mut stream: TcpStream, registry: &MetricsRegistry) {
+    let mut buf = [0u8; 1024];
+    let _ = stream.read(&mut buf);
+    let body = registry.render();
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    let _ = stream.write_all(response.as_bytes());
+}
@@ -0,0 +1,322 @@
+use crate::error::CleanerError;
+use crate::ServiceResults;
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+
+/// The pieces every notification backend needs, assembled once per run so
+/// each [`Notifier`] only has to decide how to shape them into its own
+/// native request body.
+pub struct RunSummary {
+    pub title: String,
+    pub text: String,
+    pub color_hex: String,
+}
+
+fn summarize(all_results: &[(String, ServiceResults)], aiven_project: &str) -> RunSummary {
+    let mut short_descriptions = Vec::new();
+    let mut all_deleted_indexes = Vec::new();
+    let mut has_failures = false;
+    let mut all_report_texts = Vec::new();
+
+    for (key, service_result) in all_results {
+        let failures = service_result.deletes.iter().filter(|r| !r.success).count();
+        if failures > 0 {
+            has_failures = true;
+        }
+        let status = if failures == 0 {
+            ":white_check_mark:"
+        } else {
+            ":x:"
+        };
+        short_descriptions.push(format!(
+            "{} - {}",
+            service_result.total_human_readable_msg, status
+        ));
+
+        for deleted_index in &service_result.deletes {
+            let status = if deleted_index.success {
+                ":white_check_mark:"
+            } else {
+                ":x:"
+            };
+            if deleted_index.success {
+                all_deleted_indexes.push(format!(
+                    "{} - {} ({}) - size: {} bytes",
+                    status, deleted_index.name, key, deleted_index.size
+                ));
+            } else {
+                match &deleted_index.error_code {
+                    Some(code) => all_deleted_indexes.push(format!(
+                        "{} - {} ({}) - error: {}",
+                        status, deleted_index.name, key, code
+                    )),
+                    None => all_deleted_indexes
+                        .push(format!("{} - {} ({})", status, deleted_index.name, key)),
+                }
+            }
+        }
+        if !service_result.reports.is_empty() {
+            let mut summary_texts = vec![];
+            for service_report_summary in &service_result.reports {
+                summary_texts.push(format!(
+                    "{}: {}",
+                    service_report_summary.0, service_report_summary.1
+                ));
+            }
+            let report_body = summary_texts.join("\n");
+            let report_text = format!("Summary for {} (pre-cleanup):\n{}\n", key, report_body);
+            all_report_texts.push(report_text);
+        }
+    }
+
+    let all_report_texts_value = if !all_report_texts.is_empty() {
+        format!("\n\n{}", all_report_texts.join("\n"))
+    } else {
+        "".to_string()
+    };
+    let details_text = if !all_deleted_indexes.is_empty() {
+        format!("\n\nDetails:\n\n{}", all_deleted_indexes.join("\n"))
+    } else {
+        "\n\nNot found any old indices by pre-defined rules.".to_string()
+    };
+    let text = format!(
+        "{}{}{}",
+        short_descriptions.join("\n"),
+        all_report_texts_value,
+        details_text
+    );
+    let title = format!("{} - Opensearch index cleanup", aiven_project);
+    let color_hex = if has_failures { "#E01E5A" } else { "#2EB67D" }.to_string();
+
+    RunSummary {
+        title,
+        text,
+        color_hex,
+    }
+}
+
+/// A chat/webhook backend that can turn a [`RunSummary`] into its own native
+/// request body. Adding a new platform means implementing this trait, not
+/// editing the core cleanup flow.
+pub trait Notifier {
+    fn build_payload(&self, summary: &RunSummary) -> Result<String, CleanerError>;
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct SlackNotificationData {
+    pub attachments: Vec<SlackAttachment>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct SlackAttachment {
+    pub color: String,
+    pub text: String,
+    pub title: String,
+    pub title_link: Option<String>,
+}
+
+pub struct SlackNotifier;
+
+impl Notifier for SlackNotifier {
+    fn build_payload(&self, summary: &RunSummary) -> Result<String, CleanerError> {
+        let title_link_var = env::var("NOTIFICATION_TITLE_LINK").unwrap_or_else(|_| "".to_string());
+        let title_link = match !title_link_var.is_empty() {
+            true => Some(title_link_var),
+            false => None,
+        };
+        let notification_data = SlackNotificationData {
+            attachments: vec![SlackAttachment {
+                title: summary.title.clone(),
+                title_link,
+                text: summary.text.clone(),
+                color: summary.color_hex.clone(),
+            }],
+        };
+        serde_json::to_string(&notification_data).map_err(|e| CleanerError::NotificationFailed {
+            message: e.to_string(),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct DiscordNotificationData {
+    pub embeds: Vec<DiscordEmbed>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct DiscordEmbed {
+    pub title: String,
+    pub description: String,
+    pub color: u32,
+}
+
+pub struct DiscordNotifier;
+
+impl Notifier for DiscordNotifier {
+    fn build_payload(&self, summary: &RunSummary) -> Result<String, CleanerError> {
+        let color = u32::from_str_radix(summary.color_hex.trim_start_matches('#'), 16).unwrap_or(0);
+        let notification_data = DiscordNotificationData {
+            embeds: vec![DiscordEmbed {
+                title: summary.title.clone(),
+                description: summary.text.clone(),
+                color,
+            }],
+        };
+        serde_json::to_string(&notification_data).map_err(|e| CleanerError::NotificationFailed {
+            message: e.to_string(),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+struct TeamsMessageCard {
+    #[serde(rename = "@type")]
+    pub card_type: String,
+    #[serde(rename = "@context")]
+    pub context: String,
+    pub summary: String,
+    pub title: String,
+    pub text: String,
+    #[serde(rename = "themeColor")]
+    pub theme_color: String,
+}
+
+pub struct TeamsNotifier;
+
+impl Notifier for TeamsNotifier {
+    fn build_payload(&self, summary: &RunSummary) -> Result<String, CleanerError> {
+        let card = TeamsMessageCard {
+            card_type: "MessageCard".to_string(),
+            context: "http://schema.org/extensions".to_string(),
+            summary: summary.title.clone(),
+            title: summary.title.clone(),
+            text: summary.text.clone(),
+            theme_color: summary.color_hex.trim_start_matches('#').to_string(),
+        };
+        serde_json::to_string(&card).map_err(|e| CleanerError::NotificationFailed {
+            message: e.to_string(),
+        })
+    }
+}
+
+/// Fills in a user-supplied template with `{{title}}`, `{{text}}`, and
+/// `{{color}}` placeholders, for chat/webhook platforms that don't match
+/// Slack, Discord, or Teams.
+pub struct GenericNotifier {
+    pub template: String,
+}
+
+impl GenericNotifier {
+    pub fn from_env() -> Result<Self, CleanerError> {
+        if let Ok(template_file) = env::var("NOTIFICATION_TEMPLATE_FILE") {
+            let template = fs::read_to_string(&template_file).map_err(|e| {
+                CleanerError::NotificationFailed {
+                    message: format!("could not read NOTIFICATION_TEMPLATE_FILE: {}", e),
+                }
+            })?;
+            return Ok(GenericNotifier { template });
+        }
+        let template = env::var("NOTIFICATION_TEMPLATE").map_err(|_| CleanerError::NotificationFailed {
+            message: "NOTIFICATION_KIND=generic requires NOTIFICATION_TEMPLATE or NOTIFICATION_TEMPLATE_FILE"
+                .to_string(),
+        })?;
+        Ok(GenericNotifier { template })
+    }
+}
+
+impl Notifier for GenericNotifier {
+    fn build_payload(&self, summary: &RunSummary) -> Result<String, CleanerError> {
+        let payload = self
+            .template
+            .replace("{{title}}", &summary.title)
+            .replace("{{text}}", &summary.text)
+            .replace("{{color}}", &summary.color_hex);
+        Ok(payload)
+    }
+}
+
+fn notifier_for_kind(kind: &str) -> Result<Box<dyn Notifier>, CleanerError> {
+    match kind {
+        "slack" => Ok(Box::new(SlackNotifier)),
+        "discord" => Ok(Box::new(DiscordNotifier)),
+        "teams" => Ok(Box::new(TeamsNotifier)),
+        "generic" => Ok(Box::new(GenericNotifier::from_env()?)),
+        other => Err(CleanerError::NotificationFailed {
+            message: format!("unknown NOTIFICATION_KIND '{}'", other),
+        }),
+    }
+}
+
+fn webhook_url_for_kind(kind: &str) -> Option<String> {
+    env::var(format!("{}_WEBHOOK_URL", kind.to_uppercase()))
+        .ok()
+        .or_else(|| env::var("NOTIFICATION_WEBHOOK_URL").ok())
+        .filter(|url| !url.is_empty())
+}
+
+/// Builds and sends one request per configured `NOTIFICATION_KIND` entry
+/// (comma-separated, defaults to `slack`), so multiple notifiers can fire
+/// for the same run. A kind with no webhook URL configured is skipped. Each
+/// kind is attempted independently, so a failing webhook doesn't stop the
+/// others from firing; any failures are returned together once every kind
+/// has been tried.
+pub async fn send_all(
+    all_results: &[(String, ServiceResults)],
+    aiven_project: &str,
+) -> Result<(), CleanerError> {
+    let kinds = env::var("NOTIFICATION_KIND").unwrap_or_else(|_| "slack".to_string());
+    let summary = summarize(all_results, aiven_project);
+    let client = reqwest::Client::new();
+    let mut errors = Vec::new();
+
+    for kind in kinds.split(',').map(|k| k.trim()).filter(|k| !k.is_empty()) {
+        let webhook_url = match webhook_url_for_kind(kind) {
+            Some(url) => url,
+            None => continue,
+        };
+        if let Err(err) = send_one(&client, kind, &webhook_url, &summary).await {
+            warn!("Notification via '{}' failed: {}", kind, err);
+            errors.push(err.to_string());
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(CleanerError::NotificationFailed {
+            message: errors.join("; "),
+        })
+    }
+}
+
+async fn send_one(
+    client: &reqwest::Client,
+    kind: &str,
+    webhook_url: &str,
+    summary: &RunSummary,
+) -> Result<(), CleanerError> {
+    let notifier = notifier_for_kind(kind)?;
+    let body = notifier.build_payload(summary)?;
+    let url = reqwest::Url::parse(webhook_url).map_err(|e| CleanerError::NotificationFailed {
+        message: format!("invalid webhook URL for '{}': {}", kind, e),
+    })?;
+    let res = client
+        .post(url)
+        .header("Content-type", "application/json")
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| CleanerError::NotificationFailed {
+            message: e.to_string(),
+        })?;
+    if !res.status().is_success() {
+        let status = res.status();
+        let text = res.text().await.unwrap_or_default();
+        return Err(CleanerError::NotificationFailed {
+            message: format!("{} notifier response {}: {}", kind, status, text),
+        });
+    }
+    Ok(())
+}
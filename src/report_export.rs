@@ -0,0 +1,132 @@
+use crate::error::CleanerError;
+use crate::ServiceResults;
+use serde::Serialize;
+use std::env;
+use std::fs::File;
+use std::io::Write;
+
+/// One row of the per-index export, shared by the `csv` and `jsonl` formats.
+#[derive(Serialize)]
+struct ExportedIndexRecord<'a> {
+    service: &'a str,
+    index_name: &'a str,
+    size_bytes: u64,
+    success: bool,
+    matched_rule: &'a str,
+    error_code: &'a str,
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains(',') || value.contains('"') || value.contains('\n') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn open_writer(path: &str) -> Result<Box<dyn Write>, CleanerError> {
+    let file = File::create(path).map_err(|e| CleanerError::ReportExportFailed {
+        message: format!("could not create report file '{}': {}", path, e),
+    })?;
+    if path.ends_with(".gz") {
+        Ok(Box::new(flate2::write::GzEncoder::new(
+            file,
+            flate2::Compression::default(),
+        )))
+    } else if path.ends_with(".zst") {
+        let encoder = zstd::Encoder::new(file, 0)
+            .map_err(|e| CleanerError::ReportExportFailed {
+                message: format!("could not start zstd encoder: {}", e),
+            })?
+            .auto_finish();
+        Ok(Box::new(encoder))
+    } else {
+        Ok(Box::new(file))
+    }
+}
+
+/// Writes the run to `REPORT_PATH` in the format named by `REPORT_FORMAT`
+/// (`json`, `jsonl`, or `csv`; defaults to `json`). `json` holds the whole
+/// run (per-service summaries, per-index deletions with sizes and success
+/// flags, totals); `jsonl` and `csv` hold one record per index only, without
+/// the summaries or totals. The
+/// output is gzip- or zstd-compressed when `REPORT_PATH` ends in `.gz` or
+/// `.zst`. A no-op when `REPORT_PATH` isn't set.
+pub fn export(all_results: &[(String, ServiceResults)]) -> Result<(), CleanerError> {
+    let path = env::var("REPORT_PATH").unwrap_or_else(|_| "".to_string());
+    if path.is_empty() {
+        return Ok(());
+    }
+    let format = env::var("REPORT_FORMAT").unwrap_or_else(|_| "json".to_string());
+    let mut writer = open_writer(&path)?;
+
+    match format.as_str() {
+        "json" => {
+            let json =
+                serde_json::to_vec_pretty(all_results).map_err(|e| CleanerError::ReportExportFailed {
+                    message: e.to_string(),
+                })?;
+            writer
+                .write_all(&json)
+                .map_err(|e| CleanerError::ReportExportFailed {
+                    message: e.to_string(),
+                })?;
+        }
+        "jsonl" => {
+            for (service, results) in all_results {
+                for deleted in &results.deletes {
+                    let record = ExportedIndexRecord {
+                        service,
+                        index_name: &deleted.name,
+                        size_bytes: deleted.size,
+                        success: deleted.success,
+                        matched_rule: deleted.matched_rule.as_deref().unwrap_or(""),
+                        error_code: deleted.error_code.as_deref().unwrap_or(""),
+                    };
+                    let line =
+                        serde_json::to_string(&record).map_err(|e| CleanerError::ReportExportFailed {
+                            message: e.to_string(),
+                        })?;
+                    writeln!(writer, "{}", line).map_err(|e| CleanerError::ReportExportFailed {
+                        message: e.to_string(),
+                    })?;
+                }
+            }
+        }
+        "csv" => {
+            writeln!(
+                writer,
+                "service,index_name,size_bytes,success,matched_rule,error_code"
+            )
+            .map_err(|e| CleanerError::ReportExportFailed {
+                message: e.to_string(),
+            })?;
+            for (service, results) in all_results {
+                for deleted in &results.deletes {
+                    writeln!(
+                        writer,
+                        "{},{},{},{},{},{}",
+                        csv_field(service),
+                        csv_field(&deleted.name),
+                        deleted.size,
+                        deleted.success,
+                        csv_field(deleted.matched_rule.as_deref().unwrap_or("")),
+                        csv_field(deleted.error_code.as_deref().unwrap_or(""))
+                    )
+                    .map_err(|e| CleanerError::ReportExportFailed {
+                        message: e.to_string(),
+                    })?;
+                }
+            }
+        }
+        other => {
+            return Err(CleanerError::ReportExportFailed {
+                message: format!("unknown REPORT_FORMAT '{}'", other),
+            })
+        }
+    }
+
+    writer.flush().map_err(|e| CleanerError::ReportExportFailed {
+        message: e.to_string(),
+    })
+}
@@ -0,0 +1,169 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::error::CleanerError;
+
+/// Status of a per-index deletion task. The cleanup flow writes a
+/// `processing` record followed by a `succeeded` or `failed` one for each
+/// deletion; `enqueued` is not written by it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Enqueued,
+    Processing,
+    Succeeded,
+    Failed,
+}
+
+/// A single durable record of one status of a deletion attempt for one
+/// index; no record is written for the run or the service as a whole.
+/// Appended as one JSON line so a killed process leaves a readable,
+/// resumable trail behind.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskRecord {
+    pub task_id: String,
+    pub run_id: String,
+    pub service: String,
+    pub index_name: String,
+    pub rule_pattern: String,
+    pub status: TaskStatus,
+    pub timestamp_secs: u64,
+    pub error_code: Option<String>,
+}
+
+/// Appends [`TaskRecord`]s for one cleanup run to `<store_dir>/<run_id>.jsonl`.
+pub struct TaskStore {
+    run_id: String,
+    path: PathBuf,
+    next_seq: u64,
+}
+
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+impl TaskStore {
+    pub fn new(store_dir: &str, run_id: &str) -> Result<Self, CleanerError> {
+        fs::create_dir_all(store_dir).map_err(|e| CleanerError::TaskStoreFailed {
+            message: format!("could not create task store dir '{}': {}", store_dir, e),
+        })?;
+        let path = Path::new(store_dir).join(format!("{}.jsonl", run_id));
+        Ok(TaskStore {
+            run_id: run_id.to_string(),
+            path,
+            next_seq: 0,
+        })
+    }
+
+    fn next_task_id(&mut self) -> String {
+        self.next_seq += 1;
+        format!("{}-{:06}", self.run_id, self.next_seq)
+    }
+
+    /// Records a deletion attempt for a single index under the given status,
+    /// returning the record so the caller can correlate it (task id,
+    /// timestamp) with the `ServiceResult` it corresponds to.
+    pub fn record_index(
+        &mut self,
+        service: &str,
+        index_name: &str,
+        rule_pattern: &str,
+        status: TaskStatus,
+        error_code: Option<String>,
+    ) -> Result<TaskRecord, CleanerError> {
+        let record = TaskRecord {
+            task_id: self.next_task_id(),
+            run_id: self.run_id.clone(),
+            service: service.to_string(),
+            index_name: index_name.to_string(),
+            rule_pattern: rule_pattern.to_string(),
+            status,
+            timestamp_secs: now_secs(),
+            error_code,
+        };
+        self.append(&record)?;
+        Ok(record)
+    }
+
+    fn append(&self, record: &TaskRecord) -> Result<(), CleanerError> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| CleanerError::TaskStoreFailed {
+                message: format!("could not open task store file: {}", e),
+            })?;
+        let line = serde_json::to_string(record).map_err(|e| CleanerError::TaskStoreFailed {
+            message: format!("could not serialize task record: {}", e),
+        })?;
+        writeln!(file, "{}", line).map_err(|e| CleanerError::TaskStoreFailed {
+            message: format!("could not write task record: {}", e),
+        })
+    }
+}
+
+/// Scans `store_dir` for every `*.jsonl` run file, oldest first (by mtime),
+/// and folds each one's records in order into the set of `(service,
+/// index_name)` pairs currently recorded as `succeeded`, so a resumed run
+/// (`CLEANUP_RESUME=true`) can skip indices already deleted by any prior run
+/// instead of redoing (and re-attempting to delete) them. Folding across
+/// every file, not just the most recent, matters once a resumed run is itself
+/// interrupted: the second run's file holds no records for the indices it
+/// skipped, so reading it alone would lose track of what the very first run
+/// already succeeded on.
+pub fn load_succeeded_indices(
+    store_dir: &str,
+) -> Result<HashSet<(String, String)>, CleanerError> {
+    let dir = match fs::read_dir(store_dir) {
+        Ok(dir) => dir,
+        Err(_) => return Ok(HashSet::new()),
+    };
+    let mut run_files: Vec<(SystemTime, PathBuf)> = Vec::new();
+    for entry in dir {
+        let entry = entry.map_err(|e| CleanerError::TaskStoreFailed {
+            message: e.to_string(),
+        })?;
+        let path = entry.path();
+        if path.extension().map(|ext| ext == "jsonl").unwrap_or(false) {
+            let mtime = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            run_files.push((mtime, path));
+        }
+    }
+    run_files.sort_by_key(|(mtime, _)| *mtime);
+
+    let mut succeeded = HashSet::new();
+    for (_, path) in run_files {
+        let file = File::open(&path).map_err(|e| CleanerError::TaskStoreFailed {
+            message: format!("could not open previous task store file: {}", e),
+        })?;
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| CleanerError::TaskStoreFailed {
+                message: e.to_string(),
+            })?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: TaskRecord =
+                serde_json::from_str(&line).map_err(|e| CleanerError::TaskStoreFailed {
+                    message: format!("could not parse task record: {}", e),
+                })?;
+            let key = (record.service, record.index_name);
+            if record.status == TaskStatus::Succeeded {
+                succeeded.insert(key);
+            } else {
+                succeeded.remove(&key);
+            }
+        }
+    }
+    Ok(succeeded)
+}